@@ -1,6 +1,7 @@
 use nu_ansi_term as ansi;
 use std::fmt;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::time::SystemTime;
 
 use clap::{Parser, Subcommand};
@@ -22,42 +23,43 @@ enum Commands {
         /// Solution search cutoff
         #[arg(short = 's', long, default_value_t = 12)]
         max_solutions: usize,
+
+        /// Sudoku variant the grid must satisfy
+        #[arg(short = 'v', long, value_enum)]
+        variant: Option<Variant>,
     },
 
     /// Generate a Sudoku puzzle
     Make {
         /// Random seed
         seed: Option<u64>,
+
+        /// Target difficulty; generation retries until the puzzle grades
+        /// at this level, giving up with a warning after 500 attempts
+        #[arg(short = 'd', long, value_enum)]
+        difficulty: Option<Difficulty>,
+
+        /// Sudoku variant to generate
+        #[arg(short = 'v', long, value_enum)]
+        variant: Option<Variant>,
     },
 }
 
+/// Candidate digits for a cell, packed as a `u16` bitmask where bit `n`
+/// means "digit `n` is still possible". Keeping this to a single machine
+/// word (instead of `[bool; 9]`) makes the struct trivially cheap to
+/// clone during backtracking, and turns `count`/`value`/iteration into
+/// single hardware instructions.
 #[derive(Debug, Clone, Copy)]
-struct GridCellOptions([bool; 9]);
+struct GridCellOptions(u16);
 
 impl GridCellOptions {
     fn all() -> Self {
-        Self([true; 9])
-    }
-
-    fn none() -> Self {
-        Self([false; 9])
+        Self(0x1FF)
     }
 
     fn single(value: usize) -> Self {
-        let mut options = [false; 9];
-        options[value] = true;
-
-        Self(options)
-    }
-
-    fn is_set(&self, value: Option<usize>) -> bool {
-        value.map(|value| self.0[value]).unwrap_or(false)
-    }
-
-    fn set(&mut self, value: Option<usize>) {
-        if let Some(value) = value {
-            self.0[value] = true;
-        }
+        Self(1 << value)
     }
 }
 
@@ -65,11 +67,12 @@ impl Iterator for GridCellOptions {
     type Item = usize;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(option) = self.0.iter().position(|option| *option) {
-            self.0[option] = false;
-            Some(option)
-        } else {
+        if self.0 == 0 {
             None
+        } else {
+            let value = self.0.trailing_zeros() as usize;
+            self.0 &= self.0 - 1;
+            Some(value)
         }
     }
 }
@@ -102,7 +105,7 @@ impl GridCell {
     }
 
     fn count(&self) -> usize {
-        self.options.0.iter().filter(|&&x| x).count()
+        self.options.0.count_ones() as usize
     }
 
     fn options(&self) -> GridCellOptions {
@@ -110,12 +113,11 @@ impl GridCell {
     }
 
     fn value(&self) -> Option<usize> {
-        self.unique()
-            .then(|| self.options.0.iter().position(|&x| x).unwrap())
+        self.unique().then(|| self.options.0.trailing_zeros() as usize)
     }
 
     fn is_legal(&self, value: usize) -> bool {
-        self.options.0[value]
+        self.options.0 & (1 << value) != 0
     }
 
     fn set(&mut self, value: usize) {
@@ -127,16 +129,10 @@ impl GridCell {
             return 0;
         }
 
-        let mut options_removed = 0;
+        let removed = (self.options.0 & options.0).count_ones();
+        self.options.0 &= !options.0;
 
-        for (option, &to_remove) in self.options.0.iter_mut().zip(options.0.iter()) {
-            if to_remove {
-                options_removed += *option as usize;
-                *option = false;
-            }
-        }
-
-        options_removed
+        removed as usize
     }
 }
 
@@ -190,20 +186,152 @@ impl std::ops::BitAndAssign for GridDeduction {
     }
 }
 
+const MAX_DIFFICULTY_ATTEMPTS: usize = 500;
+
+/// Which set of units a grid's cells must satisfy. The standard 9 rows,
+/// 9 columns, and 9 boxes are always present; a variant only adds to
+/// them, so the solver itself needs no special-casing per variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Variant {
+    /// Rows, columns, and boxes
+    Standard,
+    /// Standard, plus the two main diagonals (X-Sudoku)
+    Diagonal,
+    /// Standard, plus four inner 3x3 windows (Hyper-Sudoku)
+    Hyper,
+}
+
 #[derive(Clone, Copy)]
-struct Grid([GridCell; 81]);
+struct Grid([GridCell; 81], Variant);
+
+/// An error produced while parsing a grid file, in either the
+/// char-stream or the coordinate-CSV format.
+#[derive(Debug)]
+enum ParseError {
+    /// The grid file could not be read (missing, unreadable, permissions).
+    Io(std::io::Error),
+    /// The coordinate-CSV format requires a `rows,columns` header, and
+    /// this crate only understands a 9x9 board.
+    MissingHeader,
+    CoordinateLine(String),
+    OutOfRange { row: usize, column: usize },
+    DuplicateCoordinate { row: usize, column: usize },
+    InvalidDigit(usize),
+    /// The char-stream format packs 81 cells in row-major order; more
+    /// `x`/digit characters than that means the stream overflows the board.
+    TooManyCells,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(error) => write!(f, "{}", error),
+            ParseError::MissingHeader => write!(f, "expected a \"9,9\" header line"),
+            ParseError::CoordinateLine(line) => {
+                write!(f, "cannot parse coordinate line: \"{}\"", line)
+            }
+            ParseError::OutOfRange { row, column } => {
+                write!(f, "coordinate ({}, {}) is out of range", row, column)
+            }
+            ParseError::DuplicateCoordinate { row, column } => {
+                write!(f, "coordinate ({}, {}) is given more than once", row, column)
+            }
+            ParseError::InvalidDigit(digit) => {
+                write!(f, "{} is not a valid digit, expected 1-9", digit)
+            }
+            ParseError::TooManyCells => {
+                write!(f, "expected 81 cells, found more than that")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
 
 impl Grid {
-    fn new<P: AsRef<Path>>(path: P) -> Self {
+    fn new<P: AsRef<Path>>(path: P, variant: Variant) -> Result<Self, ParseError> {
+        let file = std::fs::read_to_string(path).map_err(ParseError::Io)?;
+
+        let mut grid: Self = file.parse()?;
+        grid.1 = variant;
+
+        Ok(grid)
+    }
+
+    /// Reads the classic sparse coordinate-CSV format: a `9,9` header
+    /// line followed by `row,col,value` lines, with a 0-indexed row and
+    /// column and a digit value from 1 to 9.
+    fn from_csv(input: &str) -> Result<Self, ParseError> {
+        let mut cells = [GridCell::new(None); 81];
+        let mut given = [false; 81];
+
+        let mut lines = input.lines().skip_while(|line| line.trim().is_empty());
+
+        match lines.next() {
+            Some(header) if header.trim() == "9,9" => (),
+            _ => return Err(ParseError::MissingHeader),
+        }
+
+        for line in lines {
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut fields = line.splitn(3, ',');
+
+            let field = |fields: &mut std::str::SplitN<'_, char>| {
+                fields
+                    .next()
+                    .and_then(|field| field.trim().parse::<usize>().ok())
+                    .ok_or_else(|| ParseError::CoordinateLine(line.to_string()))
+            };
+
+            let row = field(&mut fields)?;
+            let column = field(&mut fields)?;
+            let value = field(&mut fields)?;
+
+            if row >= 9 || column >= 9 {
+                return Err(ParseError::OutOfRange { row, column });
+            }
+
+            if value == 0 || value > 9 {
+                return Err(ParseError::InvalidDigit(value));
+            }
+
+            let index = row * 9 + column;
+
+            if given[index] {
+                return Err(ParseError::DuplicateCoordinate { row, column });
+            }
+
+            given[index] = true;
+            cells[index] = GridCell::new(Some(value - 1));
+        }
+
+        Ok(Self(cells, Variant::Standard))
+    }
+
+    /// Reads the flat `x`/`1-9` character-stream format, where `x` marks
+    /// an empty cell and digits fill cells in row-major order.
+    fn from_char_stream(input: &str) -> Result<Self, ParseError> {
         let mut cells = [GridCell::new(None); 81];
-        let file = std::fs::read_to_string(path).expect("cannot read grid file");
 
         let mut grid_index = 0;
-        for ch in file.chars() {
+        for ch in input.chars() {
             match ch {
-                'x' => grid_index += 1,
+                'x' => {
+                    if grid_index >= cells.len() {
+                        return Err(ParseError::TooManyCells);
+                    }
+                    grid_index += 1;
+                }
 
                 '1'..='9' => {
+                    if grid_index >= cells.len() {
+                        return Err(ParseError::TooManyCells);
+                    }
                     let digit = ch as usize - '0' as usize;
                     cells[grid_index] = GridCell::new(Some(digit - 1));
                     grid_index += 1;
@@ -213,12 +341,48 @@ impl Grid {
             }
         }
 
-        Self(cells)
+        Ok(Self(cells, Variant::Standard))
+    }
+
+    fn generate(seed: u64, variant: Variant) -> Self {
+        Self::generate_targeting(seed, variant, None)
     }
 
-    fn generate(seed: u64) -> Self {
-        let mut grid = Self([GridCell::new(None); 81]);
+    /// Generates puzzles with `seed`, reseeding with a derived seed each
+    /// attempt, until one grades at `difficulty`. Each attempt steers its
+    /// digging phase toward the target (see `generate_targeting`), so
+    /// this usually converges in far fewer than `MAX_DIFFICULTY_ATTEMPTS`
+    /// tries; if it still doesn't, a warning is printed and the last
+    /// attempt is returned even though its grade may not match.
+    fn generate_with_difficulty(seed: u64, variant: Variant, difficulty: Difficulty) -> Self {
+        let mut rand = Random::new(seed);
+
+        let mut last = None;
+
+        for _ in 0..MAX_DIFFICULTY_ATTEMPTS {
+            let grid = Self::generate_targeting(rand.get(), variant, Some(difficulty));
+
+            if grid.grade() == Some(difficulty) {
+                return grid;
+            }
+
+            last = Some(grid);
+        }
+
+        eprintln!(
+            "warning: could not generate a grid grading as {:?} after {} attempts; \
+             returning the last attempt, whose difficulty does not match",
+            difficulty, MAX_DIFFICULTY_ATTEMPTS
+        );
+
+        last.expect("MAX_DIFFICULTY_ATTEMPTS is nonzero")
+    }
 
+    /// Builds and digs a puzzle. When `target` is set, any hole that
+    /// would push the puzzle's grade past `target` is filled back in, so
+    /// digging stays biased toward the requested difficulty instead of
+    /// always minimizing givens.
+    fn generate_targeting(seed: u64, variant: Variant, target: Option<Difficulty>) -> Self {
         let mut rand = Random::new(seed);
 
         let mut digits = [0, 1, 2, 3, 4, 5, 6, 7, 8];
@@ -229,15 +393,27 @@ impl Grid {
             [60, 61, 62, 69, 70, 71, 78, 79, 80],
         ];
 
-        for indices in box_indices.iter() {
-            rand.shuffle(&mut digits);
+        // The three diagonal boxes never share a row or column, so
+        // seeding them independently is always consistent under the
+        // standard row/column/box units. A variant's extra units (e.g.
+        // hyper-sudoku's windows, which clip the corners of the centre
+        // box) can make a given shuffle contradict them, so retry until
+        // the seed is consistent with the chosen variant.
+        let mut grid = loop {
+            let mut candidate = Self([GridCell::new(None); 81], variant);
 
-            for (&index, &digit) in indices.iter().zip(digits.iter()) {
-                grid.0[index].set(digit);
+            for indices in box_indices.iter() {
+                rand.shuffle(&mut digits);
+
+                for (&index, &digit) in indices.iter().zip(digits.iter()) {
+                    candidate.0[index].set(digit);
+                }
             }
-        }
 
-        grid.deduce();
+            if candidate.deduce().no_conflict() {
+                break candidate;
+            }
+        };
 
         let mut cell_indices: Vec<usize> = (0..81).into_iter().collect();
         rand.shuffle(&mut cell_indices);
@@ -280,6 +456,13 @@ impl Grid {
 
             if grid.solve(2).len() != 1 {
                 grid = backtrace;
+                continue;
+            }
+
+            if let Some(target) = target {
+                if !matches!(grid.grade(), Some(grade) if grade <= target) {
+                    grid = backtrace;
+                }
             }
         }
 
@@ -293,27 +476,28 @@ impl Grid {
     }
 
     fn solve(mut self, solutions_cutoff: usize) -> Vec<Grid> {
+        let units = Self::units(self.1);
         let mut solutions = Vec::new();
 
-        self.find_solutions(&mut solutions, solutions_cutoff);
+        self.find_solutions(&units, &mut solutions, solutions_cutoff);
 
         solutions
     }
 
-    fn find_solutions(&mut self, solutions: &mut Vec<Grid>, cutoff: usize) {
+    fn find_solutions(&mut self, units: &[Vec<usize>], solutions: &mut Vec<Grid>, cutoff: usize) {
         if solutions.len() >= cutoff {
             return;
         }
 
-        if let Some((trial_index, options)) = self.first_unsolved_cell() {
+        if let Some((trial_index, options)) = self.minimum_remaining_values_cell() {
             let backtrack = self.clone();
 
             for guess in options {
                 self.0 = backtrack.0.clone();
                 self.0[trial_index].set(guess);
 
-                if self.deduce().no_conflict() {
-                    self.find_solutions(solutions, cutoff);
+                if self.deduce_with(units).no_conflict() {
+                    self.find_solutions(units, solutions, cutoff);
                 }
             }
         } else {
@@ -321,30 +505,93 @@ impl Grid {
         }
     }
 
-    fn first_unsolved_cell(&self) -> Option<(usize, GridCellOptions)> {
+    /// Picks the unsolved cell with the fewest remaining candidates
+    /// (minimum-remaining-values), ties broken by lowest index, so the
+    /// search branches on the most-constrained cell first.
+    fn minimum_remaining_values_cell(&self) -> Option<(usize, GridCellOptions)> {
         self.0
             .iter()
             .enumerate()
-            .find_map(|(index, cell)| (!cell.unique()).then(|| (index, cell.options())))
+            .filter(|(_, cell)| !cell.unique())
+            .min_by_key(|(_, cell)| cell.count())
+            .map(|(index, cell)| (index, cell.options()))
     }
 
     fn deduce(&mut self) -> GridDeduction {
+        let units = Self::units(self.1);
+
+        self.deduce_with(&units)
+    }
+
+    /// Same as `deduce`, but takes a precomputed unit list instead of
+    /// rebuilding one. `find_solutions` calls this on every guess in its
+    /// backtracking loop, so callers that already have the grid's units
+    /// on hand (or that deduce repeatedly for the same variant) should use
+    /// this to avoid reallocating the unit list each time.
+    fn deduce_with(&mut self, units: &[Vec<usize>]) -> GridDeduction {
         let mut result = GridDeduction::Consistent;
 
         while result.is_consistent() {
             result = GridDeduction::NoChange;
 
+            for unit in units {
+                result &= self.remove_options(unit);
+            }
+
+            if result.is_consistent() {
+                continue;
+            }
+
+            for unit in units {
+                result &= self.deduce_hidden_single(unit);
+            }
+
+            if result.is_consistent() {
+                continue;
+            }
+
+            for unit in units {
+                result &= self.deduce_naked_subset(unit);
+            }
+
             for number in 0..9 {
-                result &= self.deduce_box(number);
-                result &= self.deduce_row(number);
-                result &= self.deduce_column(number);
+                result &= self.deduce_pointing_pair(number);
             }
         }
 
         result
     }
 
-    fn deduce_box(&mut self, box_number: usize) -> GridDeduction {
+    /// The units the chosen `variant` must satisfy: the standard 9
+    /// rows, 9 columns, and 9 boxes, plus whatever the variant adds.
+    fn units(variant: Variant) -> Vec<Vec<usize>> {
+        let mut units = Vec::with_capacity(27);
+
+        for number in 0..9 {
+            units.push(Self::row_indices(number).to_vec());
+            units.push(Self::column_indices(number).to_vec());
+            units.push(Self::box_indices(number).to_vec());
+        }
+
+        match variant {
+            Variant::Standard => (),
+
+            Variant::Diagonal => {
+                units.push((0..9).map(|i| i * 10).collect());
+                units.push((0..9).map(|i| (i + 1) * 8).collect());
+            }
+
+            Variant::Hyper => {
+                for &(row, column) in &[(1, 1), (1, 5), (5, 1), (5, 5)] {
+                    units.push(Self::window_indices(row, column));
+                }
+            }
+        }
+
+        units
+    }
+
+    fn box_indices(box_number: usize) -> [usize; 9] {
         let offset = (box_number / 3) * 27 + (box_number % 3) * 3;
         let mut indices = [0, 1, 2, 9, 10, 11, 18, 19, 20];
 
@@ -352,10 +599,10 @@ impl Grid {
             *index += offset;
         }
 
-        self.remove_options(&indices)
+        indices
     }
 
-    fn deduce_row(&mut self, row_number: usize) -> GridDeduction {
+    fn row_indices(row_number: usize) -> [usize; 9] {
         let offset = 9 * row_number;
         let mut indices = [0, 1, 2, 3, 4, 5, 6, 7, 8];
 
@@ -363,32 +610,44 @@ impl Grid {
             *index += offset;
         }
 
-        self.remove_options(&indices)
+        indices
     }
 
-    fn deduce_column(&mut self, column_number: usize) -> GridDeduction {
+    fn column_indices(column_number: usize) -> [usize; 9] {
         let mut indices = [0, 9, 18, 27, 36, 45, 54, 63, 72];
 
         for index in indices.iter_mut() {
             *index += column_number;
         }
 
-        self.remove_options(&indices)
+        indices
+    }
+
+    /// The 9 cells of the `start_row..start_row+3`, `start_column..start_column+3`
+    /// window used by the hyper-sudoku variant's extra regions.
+    fn window_indices(start_row: usize, start_column: usize) -> Vec<usize> {
+        (start_row..start_row + 3)
+            .flat_map(|row| (start_column..start_column + 3).map(move |column| row * 9 + column))
+            .collect()
     }
 
     fn remove_options(&mut self, indices: &[usize]) -> GridDeduction {
-        let mut set_options = GridCellOptions::none();
+        let mut seen: u16 = 0;
 
         for &index in indices {
-            let value = self.0[index].value();
+            if let Some(value) = self.0[index].value() {
+                let bit = 1 << value;
 
-            if set_options.is_set(value) {
-                return GridDeduction::Conflict;
-            }
+                if seen & bit != 0 {
+                    return GridDeduction::Conflict;
+                }
 
-            set_options.set(value);
+                seen |= bit;
+            }
         }
 
+        let set_options = GridCellOptions(seen);
+
         let options_removed: usize = indices
             .iter()
             .map(|&index| self.0[index].remove(&set_options))
@@ -400,6 +659,213 @@ impl Grid {
             GridDeduction::Consistent
         }
     }
+
+    /// Hidden single: if a digit's candidate bit appears in exactly one
+    /// cell of the unit, that cell must hold the digit, even if it still
+    /// lists other candidates.
+    fn deduce_hidden_single(&mut self, indices: &[usize]) -> GridDeduction {
+        let mut result = GridDeduction::NoChange;
+
+        for digit in 0..9 {
+            let bit = 1u16 << digit;
+
+            let mut holders = indices
+                .iter()
+                .copied()
+                .filter(|&index| self.0[index].options().0 & bit != 0);
+
+            let Some(holder) = holders.next() else {
+                return GridDeduction::Conflict;
+            };
+
+            if holders.next().is_some() {
+                continue;
+            }
+
+            if !self.0[holder].unique() {
+                self.0[holder].set(digit);
+                result &= GridDeduction::Consistent;
+            }
+        }
+
+        result
+    }
+
+    /// Naked pair/triple: if `k` cells of the unit collectively show
+    /// exactly `k` candidates between them, those candidates cannot
+    /// appear anywhere else in the unit.
+    fn deduce_naked_subset(&mut self, indices: &[usize]) -> GridDeduction {
+        let mut result = GridDeduction::NoChange;
+
+        for size in 2..=3 {
+            let candidates: Vec<usize> = indices
+                .iter()
+                .copied()
+                .filter(|&index| !self.0[index].unique() && self.0[index].count() <= size)
+                .collect();
+
+            for subset in combinations(&candidates, size) {
+                let union = subset
+                    .iter()
+                    .fold(0u16, |acc, &index| acc | self.0[index].options().0);
+
+                if union.count_ones() as usize != size {
+                    continue;
+                }
+
+                let mask = GridCellOptions(union);
+
+                for &index in indices {
+                    if subset.contains(&index) {
+                        continue;
+                    }
+
+                    if self.0[index].remove(&mask) > 0 {
+                        result &= GridDeduction::Consistent;
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Pointing pair: if every remaining position of a digit within a
+    /// box lies in a single row or column, the digit can be eliminated
+    /// from the rest of that row or column outside the box.
+    fn deduce_pointing_pair(&mut self, box_number: usize) -> GridDeduction {
+        let box_indices = Self::box_indices(box_number);
+        let mut result = GridDeduction::NoChange;
+
+        for digit in 0..9 {
+            let bit = 1u16 << digit;
+
+            let holders: Vec<usize> = box_indices
+                .iter()
+                .copied()
+                .filter(|&index| self.0[index].options().0 & bit != 0)
+                .collect();
+
+            if holders.is_empty() {
+                return GridDeduction::Conflict;
+            }
+
+            let mask = GridCellOptions(bit);
+
+            if holders.iter().all(|&index| index / 9 == holders[0] / 9) {
+                let row = holders[0] / 9;
+
+                for index in Self::row_indices(row) {
+                    if !box_indices.contains(&index) && self.0[index].remove(&mask) > 0 {
+                        result &= GridDeduction::Consistent;
+                    }
+                }
+            } else if holders.iter().all(|&index| index % 9 == holders[0] % 9) {
+                let column = holders[0] % 9;
+
+                for index in Self::column_indices(column) {
+                    if !box_indices.contains(&index) && self.0[index].remove(&mask) > 0 {
+                        result &= GridDeduction::Consistent;
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Grades a puzzle by the hardest technique required to reduce it
+    /// to a unique solution without guessing, escalating through the
+    /// strategies in the same order `deduce` applies them. Returns
+    /// `None` if pure logic cannot finish the grid.
+    fn grade(&self) -> Option<Difficulty> {
+        let units = Self::units(self.1);
+        let mut grid = *self;
+        let mut hardest = Difficulty::Easy;
+
+        loop {
+            let mut basic = GridDeduction::NoChange;
+
+            for unit in &units {
+                basic &= grid.remove_options(unit);
+            }
+
+            if !basic.no_conflict() {
+                return None;
+            }
+
+            if basic.is_consistent() {
+                continue;
+            }
+
+            let mut hidden = GridDeduction::NoChange;
+
+            for unit in &units {
+                hidden &= grid.deduce_hidden_single(unit);
+            }
+
+            if !hidden.no_conflict() {
+                return None;
+            }
+
+            if hidden.is_consistent() {
+                hardest = hardest.max(Difficulty::Medium);
+                continue;
+            }
+
+            let mut hard = GridDeduction::NoChange;
+
+            for unit in &units {
+                hard &= grid.deduce_naked_subset(unit);
+            }
+
+            for number in 0..9 {
+                hard &= grid.deduce_pointing_pair(number);
+            }
+
+            if !hard.no_conflict() {
+                return None;
+            }
+
+            if hard.is_consistent() {
+                hardest = hardest.max(Difficulty::Hard);
+                continue;
+            }
+
+            break;
+        }
+
+        grid.0.iter().all(GridCell::unique).then_some(hardest)
+    }
+}
+
+/// Generates every `size`-combination of `items`, in order.
+fn combinations(items: &[usize], size: usize) -> Vec<Vec<usize>> {
+    fn go(items: &[usize], size: usize, start: usize, current: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+        if current.len() == size {
+            out.push(current.clone());
+            return;
+        }
+
+        for i in start..items.len() {
+            current.push(items[i]);
+            go(items, size, i + 1, current, out);
+            current.pop();
+        }
+    }
+
+    let mut out = Vec::new();
+    go(items, size, 0, &mut Vec::new(), &mut out);
+    out
+}
+
+/// The target difficulty of a generated puzzle, ranked by the hardest
+/// logical technique required to solve it without guessing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
 }
 
 /// The "Belts-and-Suspenders" PRNG from the
@@ -456,6 +922,20 @@ impl Random {
     }
 }
 
+impl FromStr for Grid {
+    type Err = ParseError;
+
+    /// A bare `9,9` header on the first non-empty line selects the
+    /// coordinate-CSV format; anything else falls back to the
+    /// char-stream format.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.lines().find(|line| !line.trim().is_empty()) {
+            Some(header) if header.trim() == "9,9" => Self::from_csv(input),
+            _ => Self::from_char_stream(input),
+        }
+    }
+}
+
 impl fmt::Display for Grid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(
@@ -507,8 +987,17 @@ fn main() {
         Commands::Solve {
             grid_file,
             max_solutions,
+            variant,
         } => {
-            let grid = Grid::new(grid_file);
+            let variant = variant.unwrap_or(Variant::Standard);
+
+            let grid = match Grid::new(grid_file, variant) {
+                Ok(grid) => grid,
+                Err(error) => {
+                    eprintln!("cannot read grid file: {}", error);
+                    return;
+                }
+            };
             println!("Unsolved:\n{}", grid);
 
             let solutions = grid.solve(max_solutions);
@@ -522,7 +1011,11 @@ fn main() {
             }
         }
 
-        Commands::Make { seed } => {
+        Commands::Make {
+            seed,
+            difficulty,
+            variant,
+        } => {
             let seed = seed.unwrap_or_else(|| {
                 SystemTime::now()
                     .duration_since(SystemTime::UNIX_EPOCH)
@@ -530,7 +1023,12 @@ fn main() {
                     .as_micros() as u64
             });
 
-            let grid = Grid::generate(seed);
+            let variant = variant.unwrap_or(Variant::Standard);
+
+            let grid = match difficulty {
+                Some(difficulty) => Grid::generate_with_difficulty(seed, variant, difficulty),
+                None => Grid::generate(seed, variant),
+            };
 
             println!("{}", grid);
         }